@@ -0,0 +1,75 @@
+//! On-disk cache for royalty datasets, stored under a stable per-app data
+//! directory derived from the bundle identifier configured in
+//! `tauri.conf.json` (`app.config().tauri.bundle.identifier`). Using the
+//! bundle identifier (rather than a generic "royalties" folder) avoids
+//! collisions with other Tauri apps sharing the same machine. `main.rs`
+//! points the main window's webview `data_directory` at this same path
+//! (via [`app_data_dir`]), so cached datasets and webview-side
+//! LocalStorage/IndexedDB actually live side by side on disk.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+fn bundle_identifier(app: &AppHandle) -> String {
+    app.config().tauri.bundle.identifier.clone()
+}
+
+#[cfg(target_os = "windows")]
+fn base_data_dir(app: &AppHandle) -> PathBuf {
+    let appdata = std::env::var("APPDATA").expect("%APPDATA% is not set");
+    PathBuf::from(appdata).join(bundle_identifier(app))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn base_data_dir(app: &AppHandle) -> PathBuf {
+    let home = std::env::var("HOME").expect("$HOME is not set");
+    PathBuf::from(home).join(".local").join(bundle_identifier(app))
+}
+
+/// The per-app data directory, for `main.rs` to also use as the main
+/// window's webview `data_directory`.
+pub fn app_data_dir(app: &AppHandle) -> PathBuf {
+    base_data_dir(app)
+}
+
+fn datasets_dir(app: &AppHandle) -> io::Result<PathBuf> {
+    let dir = base_data_dir(app).join("datasets");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Keeps cache keys confined to the datasets directory: anything that
+/// isn't ASCII alphanumeric, `-`, or `_` is replaced, character by
+/// character, with `_`.
+fn sanitize_key(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Persists a royalty dataset as JSON under the per-app cache directory so
+/// it survives restarts and can still be shown while the backend is
+/// unreachable.
+#[tauri::command]
+pub fn cache_dataset(app: AppHandle, key: String, json: String) -> Result<(), String> {
+    let dir = datasets_dir(&app).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", sanitize_key(&key)));
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Loads a previously cached dataset, returning `None` if nothing has been
+/// cached for `key` yet.
+#[tauri::command]
+pub fn load_cached_dataset(app: AppHandle, key: String) -> Result<Option<String>, String> {
+    let dir = datasets_dir(&app).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", sanitize_key(&key)));
+
+    match fs::read_to_string(path) {
+        Ok(json) => Ok(Some(json)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,180 @@
+//! Optional embedded static file server used when the app is deployed
+//! air-gapped (no reachable `ROYALTIES_URL`). When enabled via
+//! `ROYALTIES_LOCAL_SERVER=1`, the bundled frontend is served from
+//! `127.0.0.1` on an auto-picked free port instead of loading an external
+//! URL.
+//!
+//! Security note: this binds to loopback only, but any other local process
+//! (or browser tab) can reach it for as long as the app is running. Only
+//! enable this mode on trusted single-user machines. Requested paths are
+//! also canonicalized and checked against the assets root before being
+//! served, so a request like `GET /../../../../etc/passwd` can't escape
+//! the assets directory even though the server itself is unauthenticated.
+
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+const DEFAULT_ASSETS_DIR: &str = "dist";
+
+fn assets_dir() -> PathBuf {
+    std::env::var("ROYALTIES_ASSETS_DIR")
+        .unwrap_or_else(|_| DEFAULT_ASSETS_DIR.to_string())
+        .into()
+}
+
+fn local_server_enabled() -> bool {
+    std::env::var("ROYALTIES_LOCAL_SERVER")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "application/javascript; charset=utf-8",
+        Some("json") => "application/json; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Resolves a requested path against `root`, falling back to `index.html`
+/// whenever the requested file doesn't exist *or* canonicalizes to
+/// somewhere outside `root` (e.g. a `..`-laden request like
+/// `/../../../../etc/passwd`). `root` must already be canonical.
+fn resolve_path(root: &Path, requested: &str) -> PathBuf {
+    let index = root.join("index.html");
+    let candidate = root.join(if requested.is_empty() { "index.html" } else { requested });
+
+    match fs::canonicalize(&candidate) {
+        Ok(canonical) if canonical.starts_with(root) && canonical.is_file() => canonical,
+        _ => index,
+    }
+}
+
+fn serve_requests(server: tiny_http::Server, root: PathBuf) {
+    for request in server.incoming_requests() {
+        let requested = request.url().trim_start_matches('/');
+        let path = resolve_path(&root, requested);
+
+        let response = match fs::read(&path) {
+            Ok(body) => {
+                let header = tiny_http::Header::from_bytes(
+                    &b"Content-Type"[..],
+                    content_type_for(&path).as_bytes(),
+                )
+                .unwrap();
+                tiny_http::Response::from_data(body).with_header(header)
+            }
+            Err(_) => tiny_http::Response::from_string("404 Not Found").with_status_code(404),
+        };
+
+        let _ = request.respond(response);
+    }
+}
+
+/// If `ROYALTIES_LOCAL_SERVER` is set, binds a static file server on an
+/// auto-picked loopback port and returns its base URL. Returns `None` when
+/// the mode is disabled, preserving the default external-URL behavior, and
+/// also returns `None` (after logging why) if the assets directory is
+/// missing or the port can't be bound, so a misconfigured air-gapped install
+/// falls back to `ROYALTIES_URL` (and so to the offline page when that is
+/// unreachable) instead of panicking at startup.
+pub fn maybe_start() -> Option<String> {
+    if !local_server_enabled() {
+        return None;
+    }
+
+    let root = match fs::canonicalize(assets_dir()) {
+        Ok(root) => root,
+        Err(e) => {
+            eprintln!("local server assets directory not found: {}", e);
+            return None;
+        }
+    };
+
+    let listener = match TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("failed to bind local asset server: {}", e);
+            return None;
+        }
+    };
+
+    let port = match listener.local_addr() {
+        Ok(addr) => addr.port(),
+        Err(e) => {
+            eprintln!("failed to read bound local asset server port: {}", e);
+            return None;
+        }
+    };
+
+    let server = match tiny_http::Server::from_listener(listener, None) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("failed to start local asset server: {}", e);
+            return None;
+        }
+    };
+
+    thread::spawn(move || serve_requests(server, root));
+
+    Some(format!("http://127.0.0.1:{}", port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a throwaway assets root under the OS temp dir with an
+    /// `index.html` and a `secret.txt` sibling one level above it, so tests
+    /// can assert that a traversal request falls back to `index.html`
+    /// instead of reaching the sibling file.
+    fn test_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("roy-local-server-test-{}", name));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("index.html"), "<html>index</html>").unwrap();
+        fs::write(root.parent().unwrap().join("secret.txt"), "top secret").unwrap();
+        fs::canonicalize(root).unwrap()
+    }
+
+    #[test]
+    fn resolve_path_serves_existing_file() {
+        let root = test_root("existing-file");
+        assert_eq!(resolve_path(&root, "index.html"), root.join("index.html"));
+    }
+
+    #[test]
+    fn resolve_path_falls_back_to_index_for_relative_traversal() {
+        let root = test_root("relative-traversal");
+        assert_eq!(
+            resolve_path(&root, "../../../../etc/passwd"),
+            root.join("index.html")
+        );
+        assert_eq!(
+            resolve_path(&root, "../secret.txt"),
+            root.join("index.html")
+        );
+    }
+
+    #[test]
+    fn resolve_path_falls_back_to_index_for_absolute_path_injection() {
+        let root = test_root("absolute-injection");
+        assert_eq!(
+            resolve_path(&root, "/etc/passwd"),
+            root.join("index.html")
+        );
+    }
+
+    #[test]
+    fn resolve_path_falls_back_to_index_for_missing_file() {
+        let root = test_root("missing-file");
+        assert_eq!(resolve_path(&root, "nonexistent.js"), root.join("index.html"));
+    }
+}
@@ -3,26 +3,216 @@
     windows_subsystem = "windows"
 )]
 
-use tauri::{Manager, WindowBuilder, WindowUrl};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State, WindowBuilder, WindowUrl};
+
+mod cache;
+mod local_server;
+#[cfg(feature = "system-tray")]
+mod tray;
+mod windows;
+
+/// Shared, mutable handle to the royalties backend URL the main window is
+/// currently pointed at, so invoke commands can read and update it at runtime.
+struct RoyaltiesUrlState(Mutex<String>);
+
+const OFFLINE_PAGE: &str = include_str!("offline.html");
+
+fn resolve_royalties_url() -> String {
+    if let Some(local_url) = local_server::maybe_start() {
+        return local_url;
+    }
+
+    std::env::var("ROYALTIES_URL").unwrap_or_else(|_| "http://localhost:3000".to_string())
+}
+
+/// Data URL for the bundled offline page, used whenever `ROYALTIES_URL` fails
+/// to parse or the backend can't be reached.
+fn offline_page_url() -> String {
+    format!(
+        "data:text/html;charset=utf-8,{}",
+        percent_encode(OFFLINE_PAGE)
+    )
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Probes the royalties backend with a short-lived GET request, returning
+/// `true` only if it responds with a success or redirect status.
+async fn probe_backend(url: &str) -> bool {
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(5)).build() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+
+    match client.get(url).send().await {
+        Ok(resp) => resp.status().is_success() || resp.status().is_redirection(),
+        Err(_) => false,
+    }
+}
+
+/// Navigates the "main" window to `url` via `window.location.replace`, since
+/// Tauri windows can't be re-pointed to a new `WindowUrl` after creation.
+///
+/// The URL is JSON-encoded rather than interpolated raw into the eval'd
+/// string: a raw `'{url}'` interpolation lets an operator-supplied endpoint
+/// (e.g. one containing a `'`) break out of the JS string literal and run
+/// arbitrary script in the main window.
+fn navigate_main_window(app: &AppHandle, url: &str) -> Result<(), String> {
+    let window = app
+        .get_window("main")
+        .ok_or_else(|| "main window not found".to_string())?;
+    let encoded_url = serde_json::to_string(url).map_err(|e| e.to_string())?;
+    window
+        .eval(&format!("window.location.replace({})", encoded_url))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_royalties_url(state: State<RoyaltiesUrlState>) -> String {
+    state.0.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_royalties_url(app: AppHandle, state: State<RoyaltiesUrlState>, url: String) -> Result<(), String> {
+    let parsed = url::Url::parse(&url).map_err(|e| format!("invalid royalties URL: {}", e))?;
+
+    *state.0.lock().unwrap() = parsed.to_string();
+    navigate_main_window(&app, parsed.as_str())
+}
+
+/// Re-probes the currently configured backend and, if it's reachable,
+/// navigates the main window away from the offline page.
+#[tauri::command]
+async fn retry_connection(app: AppHandle, state: State<'_, RoyaltiesUrlState>) -> Result<bool, String> {
+    let url = state.0.lock().unwrap().clone();
+    let healthy = probe_backend(&url).await;
+
+    if healthy {
+        navigate_main_window(&app, &url)?;
+    }
+
+    Ok(healthy)
+}
 
 fn main() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
+        .manage(RoyaltiesUrlState(Mutex::new(resolve_royalties_url())))
+        .invoke_handler(tauri::generate_handler![
+            get_royalties_url,
+            set_royalties_url,
+            retry_connection,
+            cache::cache_dataset,
+            cache::load_cached_dataset,
+            windows::open_statement_window
+        ]);
+
+    #[cfg(feature = "system-tray")]
+    let builder = builder
+        .system_tray(tray::build_tray())
+        .on_system_tray_event(tray::handle_tray_event)
+        .on_window_event(|event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event.event() {
+                if event.window().label() == "main" {
+                    event.window().hide().ok();
+                    api.prevent_close();
+                }
+            }
+        });
+
+    builder
         .setup(|app| {
-            let url = std::env::var("ROYALTIES_URL")
-                .unwrap_or_else(|_| "http://localhost:3000".to_string());
-
-            WindowBuilder::new(
-                app,
-                "main",
-                WindowUrl::External(url.parse().unwrap())
-            )
-            .title("Royalties - Whales Music")
-            .inner_size(1200.0, 800.0)
-            .min_inner_size(900.0, 600.0)
-            .build()?;
+            // Reuse the URL already resolved into `RoyaltiesUrlState` at builder
+            // construction time rather than calling `resolve_royalties_url()`
+            // again: in local-server mode that would bind a second listener on
+            // a second port, permanently diverging from the one `get_royalties_url`
+            // and `retry_connection` report.
+            let url = app.state::<RoyaltiesUrlState>().0.lock().unwrap().clone();
+            let parsed = url.parse::<url::Url>();
+
+            // Probe before navigation: only fall back to the offline page when
+            // the backend is actually unreachable, instead of always showing it
+            // first and navigating away on success (which flashed the offline
+            // page on every launch, even against a healthy backend).
+            let healthy = match &parsed {
+                Ok(_) => tauri::async_runtime::block_on(probe_backend(&url)),
+                Err(_) => {
+                    eprintln!("ROYALTIES_URL `{}` is not a valid URL, showing offline page", url);
+                    false
+                }
+            };
+
+            let initial_window_url = if healthy {
+                WindowUrl::External(parsed.as_ref().unwrap().clone())
+            } else {
+                WindowUrl::External(offline_page_url().parse().unwrap())
+            };
+
+            WindowBuilder::new(app, "main", initial_window_url)
+                .title("Royalties - Whales Music")
+                .inner_size(1200.0, 800.0)
+                .min_inner_size(900.0, 600.0)
+                .data_directory(cache::app_data_dir(&app.handle()))
+                .build()?;
+
+            if parsed.is_ok() && !healthy {
+                let app_handle = app.handle();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+
+                        // Re-read the live URL on every iteration: `set_royalties_url`
+                        // may have re-pointed the app at a different endpoint while
+                        // this loop was sleeping. Bail instead of navigating once the
+                        // backend we were watching is no longer the configured one,
+                        // so a stale URL can't yank a working session back.
+                        let current = app_handle
+                            .state::<RoyaltiesUrlState>()
+                            .0
+                            .lock()
+                            .unwrap()
+                            .clone();
+                        if current != url {
+                            break;
+                        }
+
+                        if probe_backend(&url).await {
+                            let _ = navigate_main_window(&app_handle, &url);
+                            break;
+                        }
+                    }
+                });
+            }
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(move |_app_handle, event| {
+            // Without the tray there's no way to resurface a window once every
+            // window is destroyed, so the default exit-on-last-close is kept.
+            #[cfg(feature = "system-tray")]
+            {
+                if let tauri::RunEvent::ExitRequested { api, .. } = event {
+                    api.prevent_exit();
+                }
+            }
+            #[cfg(not(feature = "system-tray"))]
+            {
+                let _ = event;
+            }
+        });
 }
@@ -0,0 +1,62 @@
+//! System tray for the royalties dashboard. Keeps the app resident so users
+//! who leave it open all day can re-open it instantly instead of relaunching,
+//! and can trigger a data refresh or switch backends without digging through
+//! the taskbar.
+
+use tauri::{AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, Wry};
+
+const SHOW_HIDE: &str = "show_hide";
+const RELOAD: &str = "reload";
+const SWITCH_ENDPOINT: &str = "switch_endpoint";
+const QUIT: &str = "quit";
+
+pub fn build_tray() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(SHOW_HIDE, "Show/Hide"))
+        .add_item(CustomMenuItem::new(RELOAD, "Reload royalties data"))
+        .add_item(CustomMenuItem::new(SWITCH_ENDPOINT, "Switch endpoint"))
+        .add_native_item(tauri::SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(QUIT, "Quit"));
+
+    SystemTray::new().with_menu(menu)
+}
+
+fn toggle_main_window(app: &AppHandle<Wry>) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+
+    match window.is_visible() {
+        Ok(true) => {
+            let _ = window.hide();
+        }
+        _ => {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+pub fn handle_tray_event(app: &AppHandle<Wry>, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => toggle_main_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            SHOW_HIDE => toggle_main_window(app),
+            RELOAD => {
+                if let Some(window) = app.get_window("main") {
+                    let _ = window.eval("window.location.reload()");
+                }
+            }
+            SWITCH_ENDPOINT => {
+                if let Some(window) = app.get_window("main") {
+                    let _ = window.eval("window.dispatchEvent(new CustomEvent('tray:switch-endpoint'))");
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            QUIT => app.exit(0),
+            _ => {}
+        },
+        _ => {}
+    }
+}
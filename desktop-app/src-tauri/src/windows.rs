@@ -0,0 +1,38 @@
+//! Support for opening extra windows pinned to specific royalty statement
+//! routes, so users can compare multiple artists' or periods' statements
+//! side by side instead of navigating back and forth in a single webview.
+//!
+//! Closing a report window does not take the app down while the main
+//! window exists, and under the `system-tray` feature `main.rs`'s
+//! `RunEvent::ExitRequested` handler keeps it resident even once every
+//! window (report or main) is closed. Without that feature, closing the
+//! last remaining window exits the app, even if it is a report window.
+
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+
+/// Opens a new window for a royalty statement, or focuses it if a window
+/// with that label is already open.
+///
+/// Always checks `app.get_window(&label)` before building: creating a
+/// window right after looking one up by the same label is a known Tauri
+/// footgun that can blow the stack, so an existing window must be focused
+/// instead of rebuilt.
+#[tauri::command]
+pub fn open_statement_window(app: AppHandle, label: String, url: String, title: String) -> Result<(), String> {
+    if let Some(existing) = app.get_window(&label) {
+        existing.show().map_err(|e| e.to_string())?;
+        existing.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    let parsed = url::Url::parse(&url).map_err(|e| format!("invalid statement URL: {}", e))?;
+
+    WindowBuilder::new(&app, label, WindowUrl::External(parsed))
+        .title(title)
+        .inner_size(1000.0, 750.0)
+        .min_inner_size(700.0, 500.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}